@@ -0,0 +1,84 @@
+use anyhow::{Context, Result};
+use browserslist::{resolve, Opts};
+use serde::Serialize;
+
+use crate::FeatureData;
+
+/// A single targeted browser/version pulled out of a resolved browserslist query.
+#[derive(Serialize)]
+pub struct TargetResult {
+    pub browser: String,
+    pub version: String,
+    pub supported: bool,
+}
+
+/// The pass/fail verdict for one feature against a resolved browserslist target list.
+#[derive(Serialize)]
+pub struct TargetReport {
+    pub total: usize,
+    pub passing: usize,
+    pub failing: Vec<TargetResult>,
+}
+
+impl TargetReport {
+    pub fn is_fully_supported(&self) -> bool {
+        self.failing.is_empty()
+    }
+}
+
+/// Resolve a browserslist query (e.g. `"last 2 versions, > 0.5%, not dead"`) into
+/// concrete `(browser, version)` targets.
+pub fn resolve_targets(query: &str) -> Result<Vec<(String, String)>> {
+    let distribs = resolve([query], &Opts::default())
+        .with_context(|| format!("Failed to resolve browserslist query: '{}'", query))?;
+
+    Ok(distribs
+        .into_iter()
+        .map(|d| (d.name().to_string(), d.version().to_string()))
+        .collect())
+}
+
+/// Check a single feature's `stats` table against the resolved targets, treating
+/// `"y"` (and `"a"` when `partial_ok` is set) as supported.
+pub fn check_feature(
+    feature: &FeatureData,
+    targets: &[(String, String)],
+    partial_ok: bool,
+) -> TargetReport {
+    let mut failing = Vec::new();
+    let mut passing = 0;
+
+    for (browser, version) in targets {
+        let supported = feature
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.get(browser))
+            .and_then(|versions| versions.get(version))
+            .map(|flag| is_flag_supported(flag, partial_ok))
+            .unwrap_or(false);
+
+        if supported {
+            passing += 1;
+        } else {
+            failing.push(TargetResult {
+                browser: browser.clone(),
+                version: version.clone(),
+                supported,
+            });
+        }
+    }
+
+    TargetReport {
+        total: targets.len(),
+        passing,
+        failing,
+    }
+}
+
+fn is_flag_supported(flag: &str, partial_ok: bool) -> bool {
+    match flag.split_whitespace().next().unwrap_or(flag) {
+        "y" => true,
+        "a" => partial_ok,
+        _ => false,
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Deserialize, Serialize, Debug)]
+struct CacheEntry {
+    fetched_at: u64,
+    data: Value,
+}
+
+/// On-disk cache for raw feature payloads (the first entry of a
+/// `get_feat_data.php` response), keyed by feature ID.
+pub struct FeatureCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl FeatureCache {
+    pub fn new(ttl: Duration) -> Result<Self> {
+        let dir = ProjectDirs::from("", "", "caniuse_cli")
+            .context("Could not determine a cache directory for this platform")?
+            .cache_dir()
+            .join("features");
+
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+
+        Ok(Self { dir, ttl })
+    }
+
+    fn path_for(&self, feature_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", feature_id))
+    }
+
+    /// Return the cached payload for `feature_id` if present and younger than the TTL.
+    pub fn get(&self, feature_id: &str) -> Option<Value> {
+        let path = self.path_for(feature_id);
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let age = Duration::from_secs(now.saturating_sub(entry.fetched_at));
+
+        if age < self.ttl {
+            Some(entry.data)
+        } else {
+            None
+        }
+    }
+
+    /// Write `data` through to disk, stamped with the current time.
+    pub fn put(&self, feature_id: &str, data: &Value) -> Result<()> {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let entry = CacheEntry {
+            fetched_at,
+            data: data.clone(),
+        };
+
+        let path = self.path_for(feature_id);
+        let serialized = serde_json::to_string(&entry)?;
+        std::fs::write(&path, serialized)
+            .with_context(|| format!("Failed to write cache file: {}", path.display()))?;
+
+        Ok(())
+    }
+}
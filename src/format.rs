@@ -0,0 +1,244 @@
+use crate::browser_target::TargetReport;
+use crate::{get_support_and_notes, get_support_emoji, BrowserSupport, FeatureData};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use tabled::Tabled;
+
+#[derive(Tabled, Serialize, Clone)]
+pub(crate) struct BrowserSupportRow {
+    pub(crate) browser: String,
+    pub(crate) support: String,
+    pub(crate) notes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Table,
+    Json,
+    Markdown,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!(
+                "unknown format '{}' (expected table, json, markdown, or csv)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Table => "table",
+            Self::Json => "json",
+            Self::Markdown => "markdown",
+            Self::Csv => "csv",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Build the normalized per-browser support rows for a feature. Every output
+/// format renders from this same list, so they never drift from each other.
+pub(crate) fn build_support_rows(feature: &FeatureData) -> Vec<BrowserSupportRow> {
+    let mut rows = Vec::new();
+
+    if let Some(support) = &feature.support {
+        for (browser, support_info) in support {
+            let (emoji, notes) = get_support_and_notes(support_info, &feature.notes_by_num);
+            let support_str = match support_info {
+                BrowserSupport::Bool(b) => b.to_string(),
+                BrowserSupport::String(s) => s.clone(),
+                BrowserSupport::Object(obj) => obj.get("version_added").and_then(|v| v.as_str()).map_or_else(
+                    || "false".to_string(),
+                    |v| {
+                        if v.contains('#') {
+                            format!("{} (see notes)", v)
+                        } else {
+                            v.to_string()
+                        }
+                    },
+                ),
+            };
+
+            rows.push(BrowserSupportRow {
+                browser: format!("{} {}", emoji, browser),
+                support: support_str,
+                notes,
+            });
+        }
+    } else if let Some(stats) = &feature.stats {
+        for (browser, versions) in stats {
+            let latest_version = versions
+                .keys()
+                .max_by(|a, b| {
+                    a.parse::<f32>()
+                        .unwrap_or(0.0)
+                        .partial_cmp(&b.parse::<f32>().unwrap_or(0.0))
+                        .unwrap()
+                })
+                .cloned()
+                .unwrap_or_default();
+            let support_value = versions.get(&latest_version).cloned().unwrap_or_default();
+            let (emoji, notes) = match support_value.split_whitespace().next() {
+                Some("a") | Some("partial") => ("🟨", "see notes".to_string()),
+                Some("y") | Some("true") => ("✅", String::new()),
+                Some("n") | Some("false") => ("❌", String::new()),
+                _ => (get_support_emoji(&support_value), String::new()),
+            };
+
+            rows.push(BrowserSupportRow {
+                browser: format!("{} {}", emoji, browser),
+                support: if support_value.contains('#') {
+                    format!("{} (see notes)", support_value)
+                } else {
+                    support_value
+                },
+                notes,
+            });
+        }
+    }
+
+    rows
+}
+
+#[derive(Serialize)]
+struct FeatureJson<'a> {
+    id: &'a str,
+    title: &'a str,
+    description: &'a str,
+    spec: &'a str,
+    mdn_url: &'a str,
+    support: &'a [BrowserSupportRow],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    targets: Option<&'a TargetReport>,
+}
+
+/// Render every feature plus its computed support rows (and, when `--browsers`
+/// was used, its target report) as a single JSON array.
+pub(crate) fn render_json(
+    feature_ids: &[String],
+    feature_data: &[FeatureData],
+    rows: &[Vec<BrowserSupportRow>],
+    reports: Option<&[TargetReport]>,
+) -> Result<String> {
+    let entries: Vec<FeatureJson> = feature_ids
+        .iter()
+        .zip(feature_data)
+        .zip(rows)
+        .enumerate()
+        .map(|(index, ((id, feature), rows))| FeatureJson {
+            id,
+            title: &feature.title,
+            description: &feature.description,
+            spec: &feature.spec,
+            mdn_url: &feature.mdn_url,
+            support: rows,
+            targets: reports.map(|reports| &reports[index]),
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&entries)?)
+}
+
+/// Render one GitHub-flavored markdown support table per feature, plus a
+/// target-browser summary when `--browsers` was used.
+pub(crate) fn render_markdown(
+    feature_data: &[FeatureData],
+    rows: &[Vec<BrowserSupportRow>],
+    reports: Option<&[TargetReport]>,
+) -> String {
+    let mut out = String::new();
+
+    for (index, (feature, rows)) in feature_data.iter().zip(rows).enumerate() {
+        out.push_str(&format!("## {}\n\n", feature.title));
+        out.push_str("| Browser | Support | Notes |\n");
+        out.push_str("| --- | --- | --- |\n");
+        for row in rows {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                row.browser,
+                row.support,
+                row.notes.replace('\n', "<br>")
+            ));
+        }
+
+        if let Some(report) = reports.map(|reports| &reports[index]) {
+            out.push_str(&format!(
+                "\n**Target browsers:** {}/{} supported\n",
+                report.passing, report.total
+            ));
+            for failing in &report.failing {
+                out.push_str(&format!("- ❌ {} {}\n", failing.browser, failing.version));
+            }
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Render `feature,browser,support,notes` rows across all features, with an
+/// extra `target_status` column appended when `--browsers` was used.
+pub(crate) fn render_csv(
+    feature_data: &[FeatureData],
+    rows: &[Vec<BrowserSupportRow>],
+    reports: Option<&[TargetReport]>,
+) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    if reports.is_some() {
+        writer.write_record(["feature", "browser", "support", "notes", "target_status"])?;
+    } else {
+        writer.write_record(["feature", "browser", "support", "notes"])?;
+    }
+
+    for (index, (feature, rows)) in feature_data.iter().zip(rows).enumerate() {
+        let target_status = reports.map(|reports| target_status_summary(&reports[index]));
+
+        for row in rows {
+            let mut record = vec![
+                feature.title.as_str(),
+                row.browser.as_str(),
+                row.support.as_str(),
+                row.notes.as_str(),
+            ];
+            if let Some(status) = &target_status {
+                record.push(status.as_str());
+            }
+            writer.write_record(&record)?;
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn target_status_summary(report: &TargetReport) -> String {
+    if report.is_fully_supported() {
+        format!("{}/{} targets supported", report.passing, report.total)
+    } else {
+        let failing = report
+            .failing
+            .iter()
+            .map(|f| format!("{} {}", f.browser, f.version))
+            .collect::<Vec<_>>()
+            .join("; ");
+        format!(
+            "{}/{} targets supported (failing: {})",
+            report.passing, report.total, failing
+        )
+    }
+}
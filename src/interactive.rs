@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use dialoguer::MultiSelect;
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::feature_data_url;
+
+/// Whether the interactive picker should actually run: the flag is on, there's
+/// more than one candidate to choose between, and stdout is a real terminal.
+pub(crate) fn should_prompt(interactive_flag: bool, candidate_count: usize) -> bool {
+    interactive_flag && candidate_count > 1 && atty::is(atty::Stream::Stdout)
+}
+
+/// Present `candidates` (id, label) pairs in a multi-select prompt and return the
+/// IDs the user picked.
+pub(crate) fn pick(candidates: &[(String, String)]) -> Result<Vec<String>> {
+    let labels: Vec<&str> = candidates.iter().map(|(_, label)| label.as_str()).collect();
+
+    let selected = MultiSelect::new()
+        .with_prompt("Multiple features matched, pick one or more")
+        .items(&labels)
+        .interact()
+        .context("Failed to read interactive selection")?;
+
+    Ok(selected
+        .into_iter()
+        .map(|i| candidates[i].0.clone())
+        .collect())
+}
+
+/// Fetch just the `title` for each feature ID, concurrently, for cheap display
+/// in the picker. Falls back to the ID itself if a lookup fails.
+pub(crate) async fn fetch_titles(client: &Client, feature_ids: &[String]) -> Vec<String> {
+    let mut titles: Vec<(usize, String)> = stream::iter(feature_ids.iter().enumerate())
+        .map(|(index, feature_id)| async move {
+            let title = fetch_title(client, feature_id)
+                .await
+                .unwrap_or_else(|_| feature_id.clone());
+            (index, title)
+        })
+        .buffer_unordered(6)
+        .collect()
+        .await;
+
+    titles.sort_by_key(|(index, _)| *index);
+    titles.into_iter().map(|(_, title)| title).collect()
+}
+
+async fn fetch_title(client: &Client, feature_id: &str) -> Result<String> {
+    let url = feature_data_url(feature_id);
+    let response: Value = client.get(&url).send().await?.json().await?;
+
+    response
+        .as_array()
+        .and_then(|arr| arr.first())
+        .and_then(|entry| entry.get("title"))
+        .and_then(|title| title.as_str())
+        .map(|title| title.to_string())
+        .context("Feature payload had no title")
+}
@@ -1,19 +1,71 @@
 
 use anyhow::{Context, Result};
 use env_logger::Env;
-use log::{debug, error, info};
+use futures::stream::{self, StreamExt};
+use log::{debug, error, info, warn};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::time::Duration;
 use structopt::StructOpt;
-use tabled::{Table, Tabled};
+use tabled::Table;
 use url::Url;
 use colored::*;
 
+mod browser_target;
+mod cache;
+mod format;
+mod interactive;
+mod offline;
+
+use format::OutputFormat;
+
+#[derive(StructOpt)]
+enum Command {
+    /// Download (or refresh) the full caniuse dataset for offline use
+    Update,
+}
+
 #[derive(StructOpt)]
 struct Cli {
-    search_term: String,
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    /// Feature name or keyword to search for (not needed with a subcommand)
+    search_term: Option<String>,
+
+    /// Check support against a browserslist query, e.g. "last 2 versions, > 0.5%, not dead"
+    #[structopt(long)]
+    browsers: Option<String>,
+
+    /// Treat partial ("a") support as passing when checking --browsers
+    #[structopt(long)]
+    partial_ok: bool,
+
+    /// Maximum number of feature lookups to run concurrently
+    #[structopt(long, default_value = "6")]
+    concurrency: usize,
+
+    /// How long a cached feature response stays valid, in hours
+    #[structopt(long = "cache-ttl", default_value = "24")]
+    cache_ttl_hours: u64,
+
+    /// Output format: table, json, markdown, or csv
+    #[structopt(long, default_value = "table")]
+    format: OutputFormat,
+
+    /// Answer entirely from the local offline dataset (see `update`), skipping the network
+    #[structopt(long)]
+    offline: bool,
+
+    /// Number of fuzzy-matched candidates to return in offline mode
+    #[structopt(long, default_value = "5")]
+    offline_limit: usize,
+
+    /// Prompt to pick from multiple matches instead of showing them all (ignored when stdout isn't a terminal)
+    #[structopt(short = "i", long)]
+    interactive: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,44 +74,109 @@ struct FeatureResponse {
     feature_ids: Vec<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
-enum BrowserSupport {
+pub(crate) enum BrowserSupport {
     Bool(bool),
     String(String),
     Object(HashMap<String, Value>),
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-struct FeatureData {
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(crate) struct FeatureData {
     #[serde(default)]
-    title: String,
+    pub(crate) title: String,
     #[serde(default)]
-    description: String,
+    pub(crate) description: String,
     #[serde(default)]
-    spec: String,
+    pub(crate) spec: String,
     #[serde(default)]
     status: String,
     #[serde(default)]
-    mdn_url: String,
+    pub(crate) mdn_url: String,
     #[serde(default)]
-    support: Option<HashMap<String, BrowserSupport>>,
+    pub(crate) support: Option<HashMap<String, BrowserSupport>>,
     #[serde(default)]
-    stats: Option<HashMap<String, HashMap<String, String>>>,
+    pub(crate) stats: Option<HashMap<String, HashMap<String, String>>>,
     #[serde(default)]
-    notes_by_num: Option<HashMap<String, String>>,
+    pub(crate) notes_by_num: Option<HashMap<String, String>>,
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
 
-#[derive(Tabled)]
-struct BrowserSupportRow {
-    browser: String,
-    support: String,
-    notes: String,
+/// A feature payload that either matched `FeatureData` exactly, or didn't and was
+/// captured as raw JSON so one malformed entry can't take down the whole run.
+#[derive(Debug)]
+enum ParsedFeature {
+    Typed(FeatureData),
+    Dynamic { feature_id: String, value: Value },
+}
+
+impl ParsedFeature {
+    /// Degrade a `Dynamic` payload into a best-effort `FeatureData`, pulling out
+    /// whatever recognizable fields are present; pass `Typed` through unchanged.
+    fn into_feature_data(self) -> FeatureData {
+        match self {
+            ParsedFeature::Typed(data) => data,
+            ParsedFeature::Dynamic { value, .. } => FeatureData {
+                title: field_str(&value, "title").unwrap_or_else(|| "(unparsed feature)".to_string()),
+                description: field_str(&value, "description").unwrap_or_default(),
+                spec: field_str(&value, "spec").unwrap_or_default(),
+                status: field_str(&value, "status").unwrap_or_default(),
+                mdn_url: field_str(&value, "mdn_url").unwrap_or_default(),
+                support: field_value(&value, "support"),
+                stats: field_value(&value, "stats"),
+                notes_by_num: field_value(&value, "notes_by_num"),
+                extra: HashMap::new(),
+            },
+        }
+    }
+}
+
+fn field_str(value: &Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(|s| s.to_string())
+}
+
+fn field_value<T: for<'de> Deserialize<'de>>(value: &Value, key: &str) -> Option<T> {
+    value.get(key).and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+/// Parse a single feature payload, falling back to a best-effort `FeatureData`
+/// (and logging a warning) if it doesn't match the expected shape. Returns
+/// whether the fallback path was taken, so callers can report soft failures.
+/// Shared by the online fetch path and the offline dataset loader so neither
+/// lets one malformed entry take down the whole batch.
+pub(crate) fn parse_feature_payload(feature_id: &str, value: Value) -> (FeatureData, bool) {
+    match serde_json::from_value::<FeatureData>(value.clone()) {
+        Ok(feature) => (feature, false),
+        Err(err) => {
+            warn!(
+                "Feature ID {} did not match the expected shape ({}); falling back to partial data",
+                feature_id, err
+            );
+            let feature = ParsedFeature::Dynamic {
+                feature_id: feature_id.to_string(),
+                value,
+            }
+            .into_feature_data();
+            (feature, true)
+        }
+    }
+}
+
+fn placeholder_feature_data(feature_id: &str, reason: &str) -> FeatureData {
+    warn!(
+        "Failed to fetch feature ID {}: {}; falling back to partial data",
+        feature_id, reason
+    );
+    ParsedFeature::Dynamic {
+        feature_id: feature_id.to_string(),
+        value: Value::Null,
+    }
+    .into_feature_data()
 }
 
-fn get_support_emoji(support_value: &str) -> &str {
+pub(crate) fn get_support_emoji(support_value: &str) -> &str {
     match support_value {
         "false" => "❌",
         s if s.parse::<f32>().is_ok() => "✅",
@@ -72,7 +189,7 @@ fn get_support_emoji(support_value: &str) -> &str {
     }
 }
 
-fn get_support_and_notes(support_info: &BrowserSupport, notes_by_num: &Option<HashMap<String, String>>) -> (String, String) {
+pub(crate) fn get_support_and_notes(support_info: &BrowserSupport, notes_by_num: &Option<HashMap<String, String>>) -> (String, String) {
     let (support_value_str, notes) = match support_info {
         BrowserSupport::Bool(b) => (b.to_string(), None),
         BrowserSupport::String(s) => (s.clone(), None),
@@ -117,103 +234,162 @@ fn get_support_and_notes(support_info: &BrowserSupport, notes_by_num: &Option<Ha
 async fn main() -> Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("debug")).init();
     let args = Cli::from_args();
-    let client = Client::new();
+
+    if let Some(Command::Update) = args.command {
+        offline::update_dataset().await?;
+        println!("{}", "Offline dataset updated.".bold().green());
+        return Ok(());
+    }
+
+    let search_term = args
+        .search_term
+        .clone()
+        .context("a search term is required unless you run the `update` subcommand")?;
 
     println!("{} {}", "🔍".bold(), "Search term:".bold().green());
-    println!("{}", args.search_term.yellow());
+    println!("{}", search_term.yellow());
+
+    let (feature_ids, feature_data) = if args.offline {
+        let dataset = offline::load_dataset()?;
+        let mut matches = offline::fuzzy_search(&dataset, &search_term, args.offline_limit);
+
+        if interactive::should_prompt(args.interactive, matches.len()) {
+            let candidates: Vec<(String, String)> = matches
+                .iter()
+                .map(|m| (m.id.clone(), format!("{} ({})", m.feature.title, m.id)))
+                .collect();
+            let picked = interactive::pick(&candidates)?;
+            matches.retain(|m| picked.contains(&m.id));
+        }
+
+        (
+            matches.iter().map(|m| m.id.clone()).collect::<Vec<_>>(),
+            matches.into_iter().map(|m| m.feature).collect::<Vec<_>>(),
+        )
+    } else {
+        let client = Client::new();
+        let mut feature_ids = get_feature_ids(&client, &search_term).await?;
+
+        if interactive::should_prompt(args.interactive, feature_ids.len()) {
+            let titles = interactive::fetch_titles(&client, &feature_ids).await;
+            let candidates: Vec<(String, String)> = feature_ids
+                .iter()
+                .cloned()
+                .zip(titles)
+                .map(|(id, title)| (id.clone(), format!("{} ({})", title, id)))
+                .collect();
+            let picked = interactive::pick(&candidates)?;
+            feature_ids.retain(|id| picked.contains(id));
+        }
+
+        let feature_cache =
+            cache::FeatureCache::new(Duration::from_secs(args.cache_ttl_hours * 3600))?;
+        let (feature_data, soft_failures) =
+            get_feature_data(&client, &feature_ids, &feature_cache, args.concurrency).await?;
+        if !soft_failures.is_empty() {
+            println!(
+                "\n{} {} feature(s) had malformed data and were rendered with partial fields: {}",
+                "⚠️ ".bold(),
+                soft_failures.len(),
+                soft_failures.join(", ")
+            );
+        }
+        (feature_ids, feature_data)
+    };
 
-    let feature_ids = get_feature_ids(&client, &args.search_term).await?;
     println!("\n{} {}", "🏷️ ".bold(), "Selected feature IDs:".bold().green());
     for id in &feature_ids {
         println!("  • {}", id.yellow());
     }
 
-    let feature_data = get_feature_data(&client, &feature_ids).await?;
-
-    println!("\n{} {}", "📊".bold(), "Feature data:".bold().green());
-    for (index, feature) in feature_data.iter().enumerate() {
-        println!("\n{} {}", "🔹".bold(), format!("Feature {}:", index + 1).bold().blue());
-        println!("  {} {}", "📌".bold(), format!("Title: {}", feature.title).bold());
-        println!("  {} Description: {}", "📝".bold(), feature.description);
-        println!("  {} Spec: {}", "📘".bold(), feature.spec);
-        println!("  {} MDN URL: {}", "🔗".bold(), feature.mdn_url);
-
-        println!("\n  {} {}", "🖥️ ".bold(), "Browser Compatibility:".bold());
-        let mut support_data = Vec::new();
-
-        if let Some(support) = &feature.support {
-            for (browser, support_info) in support {
-                let (emoji, notes) = get_support_and_notes(support_info, &feature.notes_by_num);
-                let support_str = match support_info {
-                    BrowserSupport::Bool(b) => b.to_string(),
-                    BrowserSupport::String(s) => s.clone(),
-                    BrowserSupport::Object(obj) => {
-                        obj.get("version_added")
-                            .and_then(|v| v.as_str())
-                            .map_or_else(|| "false".to_string(), |v| if v.contains("#") { format!("{} (see notes)", v) } else { v.to_string() })
-                    },
-                };
-
-                support_data.push(BrowserSupportRow {
-                    browser: format!("{} {}", emoji, browser),
-                    support: support_str,
-                    notes,
-                });
-            }
-        } else if let Some(stats) = &feature.stats {
-            for (browser, versions) in stats {
-                let latest_version = versions
-                    .keys()
-                    .max_by(|a, b| {
-                        a.parse::<f32>()
-                            .unwrap_or(0.0)
-                            .partial_cmp(&b.parse::<f32>().unwrap_or(0.0))
-                            .unwrap()
-                    })
-                    .unwrap_or(&String::new())
-                    .clone();
-                let support_value = versions.get(&latest_version).unwrap_or(&String::new()).clone();
-                let (emoji, notes) = match support_value.split_whitespace().next() {
-                    Some("a") | Some("partial") => {
-                        let notes = "see notes".to_string();
-                        ("🟨", notes)
+    let browser_targets = args
+        .browsers
+        .as_deref()
+        .map(browser_target::resolve_targets)
+        .transpose()?;
+    let browser_reports: Option<Vec<browser_target::TargetReport>> = browser_targets
+        .as_ref()
+        .map(|targets| {
+            feature_data
+                .iter()
+                .map(|feature| browser_target::check_feature(feature, targets, args.partial_ok))
+                .collect()
+        });
+    let any_target_unsupported = browser_reports
+        .as_ref()
+        .is_some_and(|reports| reports.iter().any(|r| !r.is_fully_supported()));
+
+    let support_rows: Vec<Vec<format::BrowserSupportRow>> =
+        feature_data.iter().map(format::build_support_rows).collect();
+
+    match args.format {
+        OutputFormat::Table => {
+            println!("\n{} {}", "📊".bold(), "Feature data:".bold().green());
+            for (index, feature) in feature_data.iter().enumerate() {
+                println!("\n{} {}", "🔹".bold(), format!("Feature {}:", index + 1).bold().blue());
+                println!("  {} {}", "📌".bold(), format!("Title: {}", feature.title).bold());
+                println!("  {} Description: {}", "📝".bold(), feature.description);
+                println!("  {} Spec: {}", "📘".bold(), feature.spec);
+                println!("  {} MDN URL: {}", "🔗".bold(), feature.mdn_url);
+
+                println!("\n  {} {}", "🖥️ ".bold(), "Browser Compatibility:".bold());
+                if !support_rows[index].is_empty() {
+                    let table = Table::new(support_rows[index].clone()).to_string();
+                    println!("{}", table);
+                } else {
+                    println!("  No compatibility data available.");
+                }
+
+                // Print notes_by_num if available
+                if let Some(notes) = &feature.notes_by_num {
+                    println!("\n  {} {}", "📓".bold(), "Notes:".bold());
+                    for (num, note) in notes {
+                        println!("    Note {}: {}", num, note);
                     }
-                    Some("y") | Some("true") => ("✅", String::new()),
-                    Some("n") | Some("false") => ("❌", String::new()),
-                    _ => (get_support_emoji(&support_value), String::new())
-                };
-
-                support_data.push(BrowserSupportRow {
-                    browser: format!("{} {}", emoji, browser),
-                    support: if support_value.contains("#") { format!("{} (see notes)", support_value) } else { support_value },
-                    notes,
-                });
-            }
-        }
+                }
 
-        if !support_data.is_empty() {
-            let table = Table::new(support_data).to_string();
-            println!("{}", table);
-        } else {
-            println!("  No compatibility data available.");
-        }
+                // Print other extra information
+                println!("\n  {} {}", "ℹ️ ".bold(), "Extra information:".bold());
+                for (key, value) in &feature.extra {
+                    if key != "notes_by_num" && key != "support" && key != "stats" {
+                        println!("    {}: {}", key.bold(), value);
+                    }
+                }
 
-        // Print notes_by_num if available
-        if let Some(notes) = &feature.notes_by_num {
-            println!("\n  {} {}", "📓".bold(), "Notes:".bold());
-            for (num, note) in notes {
-                println!("    Note {}: {}", num, note);
-            }
-        }
+                if let Some(reports) = &browser_reports {
+                    let report = &reports[index];
+                    println!("\n  {} {}", "🎯".bold(), "Target browsers:".bold());
+                    println!("    {}/{} targets supported", report.passing, report.total);
+                    for failing in &report.failing {
+                        println!("    {} {} {}", "❌".red(), failing.browser, failing.version);
+                    }
+                }
 
-        // Print other extra information
-        println!("\n  {} {}", "ℹ️ ".bold(), "Extra information:".bold());
-        for (key, value) in &feature.extra {
-            if key != "notes_by_num" && key != "support" && key != "stats" {
-                println!("    {}: {}", key.bold(), value);
+                println!();
             }
         }
-        println!();
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                format::render_json(&feature_ids, &feature_data, &support_rows, browser_reports.as_deref())?
+            );
+        }
+        OutputFormat::Markdown => {
+            println!(
+                "{}",
+                format::render_markdown(&feature_data, &support_rows, browser_reports.as_deref())
+            );
+        }
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                format::render_csv(&feature_data, &support_rows, browser_reports.as_deref())?
+            );
+        }
+    }
+
+    if any_target_unsupported {
+        std::process::exit(1);
     }
 
     Ok(())
@@ -253,37 +429,89 @@ async fn get_feature_ids(client: &Client, search_term: &str) -> Result<Vec<Strin
     Ok(parsed.feature_ids.into_iter().collect())
 }
 
-async fn get_feature_data(client: &Client, feature_ids: &[String]) -> Result<Vec<FeatureData>> {
-    let mut feature_data = Vec::new();
-
-    for feature_id in feature_ids {
-        info!("Fetching data for feature ID: {}", feature_id);
-
-        let url = format!(
-            "https://caniuse.com/process/get_feat_data.php?type=support-data&feat={}",
-            feature_id
-        );
-        debug!("Requesting URL: {}", url);
-
-        let response: Value = client
-            .get(&url)
-            .send()
-            .await?
-            .json()
-            .await
-            .context("Failed to parse feature data response")?;
-
-        debug!("Received response for feature ID {}: {:?}", feature_id, response);
-
-        // Parse the feature data
-        if let Some(data) = response.as_array().and_then(|arr| arr.first()) {
-            let feature: FeatureData = serde_json::from_value(data.clone())
-                .context("Failed to parse feature data")?;
-            feature_data.push(feature);
-        }
+/// Fetch every feature ID concurrently. Neither a transport-level failure
+/// (network error, non-JSON body, empty response) nor a payload that doesn't
+/// match `FeatureData` aborts the batch — each soft-fails to a best-effort
+/// `FeatureData` via `parse_feature_payload`/`placeholder_feature_data`, and
+/// the offending IDs are returned alongside the results so the caller can
+/// report them.
+async fn get_feature_data(
+    client: &Client,
+    feature_ids: &[String],
+    cache: &cache::FeatureCache,
+    concurrency: usize,
+) -> Result<(Vec<FeatureData>, Vec<String>)> {
+    let mut results: Vec<(usize, String, FeatureData, bool)> = stream::iter(feature_ids.iter().enumerate())
+        .map(|(index, feature_id)| async move {
+            let (feature, is_fallback) = match fetch_feature_payload(client, feature_id, cache).await {
+                Ok(data) => parse_feature_payload(feature_id, data),
+                Err(err) => (placeholder_feature_data(feature_id, &err.to_string()), true),
+            };
+            (index, feature_id.clone(), feature, is_fallback)
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    results.sort_by_key(|(index, ..)| *index);
+
+    let mut soft_failures = Vec::new();
+    let feature_data = results
+        .into_iter()
+        .map(|(_, feature_id, feature, is_fallback)| {
+            if is_fallback {
+                soft_failures.push(feature_id);
+            }
+            feature
+        })
+        .collect();
+
+    Ok((feature_data, soft_failures))
+}
 
-        info!("Successfully parsed data for feature ID: {}", feature_id);
+/// Fetch a single feature's raw JSON payload, serving it from the disk cache when
+/// a fresh enough entry exists and writing through on a miss.
+async fn fetch_feature_payload(
+    client: &Client,
+    feature_id: &str,
+    cache: &cache::FeatureCache,
+) -> Result<Value> {
+    if let Some(cached) = cache.get(feature_id) {
+        debug!("Cache hit for feature ID: {}", feature_id);
+        return Ok(cached);
     }
 
-    Ok(feature_data)
+    info!("Fetching data for feature ID: {}", feature_id);
+
+    let url = feature_data_url(feature_id);
+    debug!("Requesting URL: {}", url);
+
+    let response: Value = client
+        .get(&url)
+        .send()
+        .await?
+        .json()
+        .await
+        .context("Failed to parse feature data response")?;
+
+    debug!("Received response for feature ID {}: {:?}", feature_id, response);
+
+    let data = response
+        .as_array()
+        .and_then(|arr| arr.first())
+        .cloned()
+        .with_context(|| format!("No feature data returned for feature ID: {}", feature_id))?;
+
+    cache.put(feature_id, &data)?;
+
+    info!("Successfully fetched data for feature ID: {}", feature_id);
+
+    Ok(data)
+}
+
+pub(crate) fn feature_data_url(feature_id: &str) -> String {
+    format!(
+        "https://caniuse.com/process/get_feat_data.php?type=support-data&feat={}",
+        feature_id
+    )
 }
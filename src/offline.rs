@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use log::warn;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{parse_feature_payload, FeatureData};
+
+const FULL_DATASET_URL: &str =
+    "https://raw.githubusercontent.com/Fyrd/caniuse/main/fulldata-json/data-2.0.json";
+
+#[derive(Deserialize)]
+struct FullDataset {
+    data: HashMap<String, Value>,
+}
+
+/// A single candidate returned by `fuzzy_search`.
+pub(crate) struct Match {
+    pub(crate) id: String,
+    pub(crate) feature: FeatureData,
+}
+
+fn dataset_path() -> Result<PathBuf> {
+    let dir = ProjectDirs::from("", "", "caniuse_cli")
+        .context("Could not determine a cache directory for this platform")?
+        .cache_dir()
+        .to_path_buf();
+
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+
+    Ok(dir.join("fulldata-json-2.0.json"))
+}
+
+/// Download the full caniuse dataset and store it in the local cache for offline use.
+pub(crate) async fn update_dataset() -> Result<()> {
+    let client = Client::new();
+    let body = client
+        .get(FULL_DATASET_URL)
+        .send()
+        .await?
+        .text()
+        .await
+        .context("Failed to download the full caniuse dataset")?;
+
+    let path = dataset_path()?;
+    std::fs::write(&path, body)
+        .with_context(|| format!("Failed to write dataset to {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Load the cached full dataset, pointing the user at `update` if it hasn't been fetched yet.
+/// Each entry is parsed independently via `parse_feature_payload`, so one
+/// malformed feature in the dump degrades gracefully instead of failing the
+/// whole load.
+pub(crate) fn load_dataset() -> Result<HashMap<String, FeatureData>> {
+    let path = dataset_path()?;
+    let body = std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "No offline dataset found at {}; run `caniuse update` first",
+            path.display()
+        )
+    })?;
+
+    let dataset: FullDataset =
+        serde_json::from_str(&body).context("Failed to parse the cached full caniuse dataset")?;
+
+    let mut soft_failures = 0usize;
+    let features = dataset
+        .data
+        .into_iter()
+        .map(|(id, value)| {
+            let (feature, is_fallback) = parse_feature_payload(&id, value);
+            if is_fallback {
+                soft_failures += 1;
+            }
+            (id, feature)
+        })
+        .collect();
+
+    if soft_failures > 0 {
+        warn!(
+            "{} offline dataset entr{} had malformed data and were loaded with partial fields",
+            soft_failures,
+            if soft_failures == 1 { "y" } else { "ies" }
+        );
+    }
+
+    Ok(features)
+}
+
+/// Rank features against `query` using a single searchable string per feature
+/// (key + title + description), returning the top `limit` matches.
+pub(crate) fn fuzzy_search(
+    dataset: &HashMap<String, FeatureData>,
+    query: &str,
+    limit: usize,
+) -> Vec<Match> {
+    let matcher = SkimMatcherV2::default();
+
+    let mut scored: Vec<(i64, &String, &FeatureData)> = dataset
+        .iter()
+        .filter_map(|(id, feature)| {
+            let haystack = string_for_matching(id, feature);
+            matcher
+                .fuzzy_match(&haystack, query)
+                .map(|score| (score, id, feature))
+        })
+        .collect();
+
+    // Break score ties by ID so results are deterministic across runs
+    // (HashMap iteration order is randomized per process).
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, id, feature)| Match {
+            id: id.clone(),
+            feature: feature.clone(),
+        })
+        .collect()
+}
+
+fn string_for_matching(id: &str, feature: &FeatureData) -> String {
+    format!("{} {} {}", id, feature.title, feature.description)
+}